@@ -22,14 +22,20 @@
  *
  */
 
+use std::collections::HashMap;
 use std::ffi::{CStr, CString};
 use std::fmt;
 use std::os::raw::{c_char, c_int};
 use std::ptr;
 
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Serialize};
+
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
 #[repr(i32)]
-#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
 pub enum Symbology {
+    #[default]
     None = -1,
     DataBarOmni = 0,
     DataBarTruncated,
@@ -49,7 +55,7 @@ pub enum Symbology {
 }
 
 #[repr(u32)]
-#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
 pub enum Validation {
     MutexAis = 0,
     RequisiteAis,
@@ -90,100 +96,557 @@ extern "C" {
     fn gs1_encoder_getHRI(ctx: *const u32, hri: *const *const *const c_char) -> c_int;
 }
 
-pub struct GS1Encoder {
+/// A single Application Identifier element extracted from the HRI/AI data of
+/// a processed message, with the AI, its value and (optionally) its data
+/// title split into their own fields instead of a pre-formatted string.
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ExtractedAI {
+    pub ai: String,
+    pub value: String,
+    pub data_title: Option<String>,
+    /// Whether this element was terminated by an explicit FNC1 separator in
+    /// the encoded data string, i.e. it is a variable-length element that is
+    /// not the final element of the message.
+    pub fnc1_required: bool,
+}
+
+/// The operations that a `GS1Encoder` delegates to. The default
+/// implementation, `FfiBackend`, calls into the native `gs1_encoder_*` C
+/// library; `MockBackend` provides an in-crate alternative so that code
+/// built on top of `GS1Encoder` can be unit-tested without linking that
+/// library.
+pub trait GS1Backend {
+    fn get_version(&self) -> String;
+    fn get_err_msg(&self) -> String;
+    fn get_err_markup(&self) -> String;
+    fn get_sym(&self) -> Symbology;
+    fn set_sym(&mut self, sym: Symbology) -> bool;
+    fn get_add_check_digit(&self) -> bool;
+    fn set_add_check_digit(&mut self, value: bool) -> bool;
+    fn get_permit_unknown_ais(&self) -> bool;
+    fn set_permit_unknown_ais(&mut self, value: bool) -> bool;
+    fn get_permit_zero_suppressed_gtin_in_dl_uris(&self) -> bool;
+    fn set_permit_zero_suppressed_gtin_in_dl_uris(&mut self, value: bool) -> bool;
+    fn get_validation_enabled(&self, validation: Validation) -> bool;
+    fn set_validation_enabled(&mut self, validation: Validation, enabled: bool) -> bool;
+    fn get_include_data_titles_in_hri(&self) -> bool;
+    fn set_include_data_titles_in_hri(&mut self, value: bool) -> bool;
+    fn get_data_str(&self) -> String;
+    fn set_data_str(&mut self, value: &str) -> bool;
+    fn get_ai_data_str(&self) -> Option<String>;
+    fn set_ai_data_str(&mut self, value: &str) -> bool;
+    fn get_scan_data(&self) -> Option<String>;
+    fn set_scan_data(&mut self, value: &str) -> bool;
+    fn get_dl_uri(&self, stem: Option<&str>) -> Option<String>;
+    fn get_dl_ignored_query_params(&self) -> Vec<String>;
+    fn get_hri(&self) -> Vec<String>;
+}
+
+/// The default `GS1Backend`, which calls into the native `gs1_encoder_*` C
+/// library via FFI.
+struct FfiBackend {
     ctx: *mut u32,
 }
 
-impl GS1Encoder {
+impl FfiBackend {
+    fn new() -> Result<Self, GS1EncoderError> {
+        let ctx = unsafe { gs1_encoder_init(ptr::null()) as *mut u32 };
+        if ctx.is_null() {
+            return Err(GS1EncoderError::GS1GeneralError(
+                "Failed to initialise the native library".to_string(),
+            ));
+        }
+        Ok(FfiBackend { ctx })
+    }
+}
+
+impl GS1Backend for FfiBackend {
+    fn get_version(&self) -> String {
+        let c_str: &CStr = unsafe { CStr::from_ptr(gs1_encoder_getVersion()) };
+        c_str.to_str().unwrap().to_owned()
+    }
+
     fn get_err_msg(&self) -> String {
         let c_str: &CStr = unsafe { CStr::from_ptr(gs1_encoder_getErrMsg(self.ctx)) };
         c_str.to_str().unwrap().to_owned()
     }
 
-    pub fn new() -> Result<Self, GS1EncoderError> {
-        let mut gs1encoder = GS1Encoder {
-            ctx: ptr::null_mut(),
-        };
-        gs1encoder.ctx = unsafe { gs1_encoder_init(ptr::null()) as *mut u32 };
-        if gs1encoder.ctx.is_null() {
-            return Err(GS1EncoderError::GS1GeneralError(
-                "Failed to initialise the native library".to_string(),
-            ));
+    fn get_err_markup(&self) -> String {
+        let c_str: &CStr = unsafe { CStr::from_ptr(gs1_encoder_getErrMarkup(self.ctx)) };
+        c_str.to_str().unwrap().to_owned()
+    }
+
+    fn get_sym(&self) -> Symbology {
+        let raw = unsafe { gs1_encoder_getSym(self.ctx) };
+        unsafe { std::mem::transmute(raw) }
+    }
+
+    fn set_sym(&mut self, sym: Symbology) -> bool {
+        unsafe { gs1_encoder_setSym(self.ctx, sym as c_int) }
+    }
+
+    fn get_add_check_digit(&self) -> bool {
+        unsafe { gs1_encoder_getAddCheckDigit(self.ctx) }
+    }
+
+    fn set_add_check_digit(&mut self, value: bool) -> bool {
+        unsafe { gs1_encoder_setAddCheckDigit(self.ctx, value) }
+    }
+
+    fn get_permit_unknown_ais(&self) -> bool {
+        unsafe { gs1_encoder_getPermitUnknownAIs(self.ctx) }
+    }
+
+    fn set_permit_unknown_ais(&mut self, value: bool) -> bool {
+        unsafe { gs1_encoder_setPermitUnknownAIs(self.ctx, value) }
+    }
+
+    fn get_permit_zero_suppressed_gtin_in_dl_uris(&self) -> bool {
+        unsafe { gs1_encoder_getPermitZeroSuppressedGTINinDLuris(self.ctx) }
+    }
+
+    fn set_permit_zero_suppressed_gtin_in_dl_uris(&mut self, value: bool) -> bool {
+        unsafe { gs1_encoder_setPermitZeroSuppressedGTINinDLuris(self.ctx, value) }
+    }
+
+    fn get_validation_enabled(&self, validation: Validation) -> bool {
+        unsafe { gs1_encoder_getValidationEnabled(self.ctx, validation as c_int) }
+    }
+
+    fn set_validation_enabled(&mut self, validation: Validation, enabled: bool) -> bool {
+        unsafe { gs1_encoder_setValidationEnabled(self.ctx, validation as c_int, enabled) }
+    }
+
+    fn get_include_data_titles_in_hri(&self) -> bool {
+        unsafe { gs1_encoder_getIncludeDataTitlesInHRI(self.ctx) }
+    }
+
+    fn set_include_data_titles_in_hri(&mut self, value: bool) -> bool {
+        unsafe { gs1_encoder_setIncludeDataTitlesInHRI(self.ctx, value) }
+    }
+
+    fn get_data_str(&self) -> String {
+        let c_str: &CStr = unsafe { CStr::from_ptr(gs1_encoder_getDataStr(self.ctx)) };
+        c_str.to_str().unwrap().to_owned()
+    }
+
+    fn set_data_str(&mut self, value: &str) -> bool {
+        let c_str = CString::new(value).unwrap();
+        unsafe { gs1_encoder_setDataStr(self.ctx, c_str.as_ptr() as *const c_char) }
+    }
+
+    fn get_ai_data_str(&self) -> Option<String> {
+        let ptr = unsafe { gs1_encoder_getAIdataStr(self.ctx) };
+        if ptr.is_null() {
+            return None;
         }
-        Ok(gs1encoder)
+        let c_str: &CStr = unsafe { CStr::from_ptr(ptr) };
+        Some(c_str.to_str().unwrap().to_owned())
     }
 
-    pub fn free(&mut self) {
+    fn set_ai_data_str(&mut self, value: &str) -> bool {
+        let c_str = CString::new(value).unwrap();
+        unsafe { gs1_encoder_setAIdataStr(self.ctx, c_str.as_ptr() as *const c_char) }
+    }
+
+    fn get_scan_data(&self) -> Option<String> {
+        let ptr = unsafe { gs1_encoder_getScanData(self.ctx) };
+        if ptr.is_null() {
+            return None;
+        }
+        let c_str: &CStr = unsafe { CStr::from_ptr(ptr) };
+        Some(c_str.to_str().unwrap().to_owned())
+    }
+
+    fn set_scan_data(&mut self, value: &str) -> bool {
+        let c_str = CString::new(value).unwrap();
+        unsafe { gs1_encoder_setScanData(self.ctx, c_str.as_ptr() as *const c_char) }
+    }
+
+    fn get_dl_uri(&self, stem: Option<&str>) -> Option<String> {
+        let c_stem = stem.map(|s| CString::new(s).unwrap());
+        let stem_ptr = c_stem
+            .as_ref()
+            .map_or(ptr::null(), |s| s.as_ptr() as *const c_char);
+        let ptr = unsafe { gs1_encoder_getDLuri(self.ctx, stem_ptr) };
+        if ptr.is_null() {
+            return None;
+        }
+        let c_str: &CStr = unsafe { CStr::from_ptr(ptr) };
+        Some(c_str.to_str().unwrap().to_owned())
+    }
+
+    fn get_dl_ignored_query_params(&self) -> Vec<String> {
+        let ptr: *const *const c_char = ptr::null();
+        let size = unsafe { gs1_encoder_getDLignoredQueryParams(self.ctx, &ptr) };
+        let mut params = Vec::with_capacity(size as usize);
+        for i in 0..size {
+            let c_buf = unsafe { ptr::read(ptr.offset(i as isize)) };
+            let c_str: &CStr = unsafe { CStr::from_ptr(c_buf) };
+            params.push(c_str.to_str().unwrap().to_owned());
+        }
+        params
+    }
+
+    fn get_hri(&self) -> Vec<String> {
+        let ptr: *const *const c_char = ptr::null();
+        let size = unsafe { gs1_encoder_getHRI(self.ctx, &ptr) };
+        let mut hri = Vec::with_capacity(size as usize);
+        for i in 0..size {
+            let c_buf = unsafe { ptr::read(ptr.offset(i as isize)) };
+            let c_str: &CStr = unsafe { CStr::from_ptr(c_buf) };
+            hri.push(c_str.to_str().unwrap().to_owned());
+        }
+        hri
+    }
+}
+
+impl Drop for FfiBackend {
+    fn drop(&mut self) {
         if !self.ctx.is_null() {
             unsafe { gs1_encoder_free(self.ctx) };
             self.ctx = ptr::null_mut();
         }
     }
+}
+
+/// The backend installed by `GS1Encoder::free()` in place of whatever
+/// backend was released. Using the encoder afterwards is a programming
+/// error, so every operation panics rather than quietly returning blank
+/// data from a backend that looks valid.
+struct FreedBackend;
+
+impl FreedBackend {
+    fn panic_used_after_free() -> ! {
+        panic!("GS1Encoder: used after free() was called");
+    }
+}
+
+impl GS1Backend for FreedBackend {
+    fn get_version(&self) -> String {
+        Self::panic_used_after_free()
+    }
+    fn get_err_msg(&self) -> String {
+        Self::panic_used_after_free()
+    }
+    fn get_err_markup(&self) -> String {
+        Self::panic_used_after_free()
+    }
+    fn get_sym(&self) -> Symbology {
+        Self::panic_used_after_free()
+    }
+    fn set_sym(&mut self, _sym: Symbology) -> bool {
+        Self::panic_used_after_free()
+    }
+    fn get_add_check_digit(&self) -> bool {
+        Self::panic_used_after_free()
+    }
+    fn set_add_check_digit(&mut self, _value: bool) -> bool {
+        Self::panic_used_after_free()
+    }
+    fn get_permit_unknown_ais(&self) -> bool {
+        Self::panic_used_after_free()
+    }
+    fn set_permit_unknown_ais(&mut self, _value: bool) -> bool {
+        Self::panic_used_after_free()
+    }
+    fn get_permit_zero_suppressed_gtin_in_dl_uris(&self) -> bool {
+        Self::panic_used_after_free()
+    }
+    fn set_permit_zero_suppressed_gtin_in_dl_uris(&mut self, _value: bool) -> bool {
+        Self::panic_used_after_free()
+    }
+    fn get_validation_enabled(&self, _validation: Validation) -> bool {
+        Self::panic_used_after_free()
+    }
+    fn set_validation_enabled(&mut self, _validation: Validation, _enabled: bool) -> bool {
+        Self::panic_used_after_free()
+    }
+    fn get_include_data_titles_in_hri(&self) -> bool {
+        Self::panic_used_after_free()
+    }
+    fn set_include_data_titles_in_hri(&mut self, _value: bool) -> bool {
+        Self::panic_used_after_free()
+    }
+    fn get_data_str(&self) -> String {
+        Self::panic_used_after_free()
+    }
+    fn set_data_str(&mut self, _value: &str) -> bool {
+        Self::panic_used_after_free()
+    }
+    fn get_ai_data_str(&self) -> Option<String> {
+        Self::panic_used_after_free()
+    }
+    fn set_ai_data_str(&mut self, _value: &str) -> bool {
+        Self::panic_used_after_free()
+    }
+    fn get_scan_data(&self) -> Option<String> {
+        Self::panic_used_after_free()
+    }
+    fn set_scan_data(&mut self, _value: &str) -> bool {
+        Self::panic_used_after_free()
+    }
+    fn get_dl_uri(&self, _stem: Option<&str>) -> Option<String> {
+        Self::panic_used_after_free()
+    }
+    fn get_dl_ignored_query_params(&self) -> Vec<String> {
+        Self::panic_used_after_free()
+    }
+    fn get_hri(&self) -> Vec<String> {
+        Self::panic_used_after_free()
+    }
+}
+
+/// The mock `GS1Backend` referred to by the trait's docs. Every field is
+/// `pub` so a test can set up exactly the state it needs; setters on the
+/// mock write straight through to the corresponding field and succeed
+/// unless `fail_setters` is set.
+#[derive(Debug, Clone, Default)]
+pub struct MockBackend {
+    pub version: String,
+    pub err_msg: String,
+    pub err_markup: String,
+    pub sym: Symbology,
+    pub add_check_digit: bool,
+    pub permit_unknown_ais: bool,
+    pub permit_zero_suppressed_gtin_in_dl_uris: bool,
+    pub validation_enabled: HashMap<Validation, bool>,
+    pub include_data_titles_in_hri: bool,
+    pub data_str: String,
+    pub ai_data_str: Option<String>,
+    pub scan_data: Option<String>,
+    pub dl_uri: Option<String>,
+    pub dl_ignored_query_params: Vec<String>,
+    pub hri: Vec<String>,
+    pub fail_setters: bool,
+}
+
+impl MockBackend {
+    pub fn new() -> Self {
+        Self::default()
+    }
+}
+
+impl GS1Backend for MockBackend {
+    fn get_version(&self) -> String {
+        self.version.clone()
+    }
+
+    fn get_err_msg(&self) -> String {
+        self.err_msg.clone()
+    }
+
+    fn get_err_markup(&self) -> String {
+        self.err_markup.clone()
+    }
+
+    fn get_sym(&self) -> Symbology {
+        self.sym
+    }
+
+    fn set_sym(&mut self, sym: Symbology) -> bool {
+        if self.fail_setters {
+            return false;
+        }
+        self.sym = sym;
+        true
+    }
+
+    fn get_add_check_digit(&self) -> bool {
+        self.add_check_digit
+    }
+
+    fn set_add_check_digit(&mut self, value: bool) -> bool {
+        if self.fail_setters {
+            return false;
+        }
+        self.add_check_digit = value;
+        true
+    }
+
+    fn get_permit_unknown_ais(&self) -> bool {
+        self.permit_unknown_ais
+    }
+
+    fn set_permit_unknown_ais(&mut self, value: bool) -> bool {
+        if self.fail_setters {
+            return false;
+        }
+        self.permit_unknown_ais = value;
+        true
+    }
+
+    fn get_permit_zero_suppressed_gtin_in_dl_uris(&self) -> bool {
+        self.permit_zero_suppressed_gtin_in_dl_uris
+    }
+
+    fn set_permit_zero_suppressed_gtin_in_dl_uris(&mut self, value: bool) -> bool {
+        if self.fail_setters {
+            return false;
+        }
+        self.permit_zero_suppressed_gtin_in_dl_uris = value;
+        true
+    }
+
+    fn get_validation_enabled(&self, validation: Validation) -> bool {
+        *self.validation_enabled.get(&validation).unwrap_or(&true)
+    }
+
+    fn set_validation_enabled(&mut self, validation: Validation, enabled: bool) -> bool {
+        if self.fail_setters {
+            return false;
+        }
+        self.validation_enabled.insert(validation, enabled);
+        true
+    }
+
+    fn get_include_data_titles_in_hri(&self) -> bool {
+        self.include_data_titles_in_hri
+    }
+
+    fn set_include_data_titles_in_hri(&mut self, value: bool) -> bool {
+        if self.fail_setters {
+            return false;
+        }
+        self.include_data_titles_in_hri = value;
+        true
+    }
+
+    fn get_data_str(&self) -> String {
+        self.data_str.clone()
+    }
+
+    fn set_data_str(&mut self, value: &str) -> bool {
+        if self.fail_setters {
+            return false;
+        }
+        self.data_str = value.to_string();
+        true
+    }
+
+    fn get_ai_data_str(&self) -> Option<String> {
+        self.ai_data_str.clone()
+    }
+
+    fn set_ai_data_str(&mut self, value: &str) -> bool {
+        if self.fail_setters {
+            return false;
+        }
+        self.ai_data_str = Some(value.to_string());
+        true
+    }
+
+    fn get_scan_data(&self) -> Option<String> {
+        self.scan_data.clone()
+    }
+
+    fn set_scan_data(&mut self, value: &str) -> bool {
+        if self.fail_setters {
+            return false;
+        }
+        self.scan_data = Some(value.to_string());
+        true
+    }
+
+    fn get_dl_uri(&self, _stem: Option<&str>) -> Option<String> {
+        self.dl_uri.clone()
+    }
+
+    fn get_dl_ignored_query_params(&self) -> Vec<String> {
+        self.dl_ignored_query_params.clone()
+    }
+
+    fn get_hri(&self) -> Vec<String> {
+        self.hri.clone()
+    }
+}
+
+pub struct GS1Encoder {
+    backend: Box<dyn GS1Backend>,
+}
+
+impl GS1Encoder {
+    /// Create an encoder backed by the native `gs1_encoder_*` C library.
+    pub fn new() -> Result<Self, GS1EncoderError> {
+        Ok(GS1Encoder {
+            backend: Box::new(FfiBackend::new()?),
+        })
+    }
+
+    /// Create an encoder backed by a custom `GS1Backend`.
+    pub fn with_backend(backend: Box<dyn GS1Backend>) -> Self {
+        GS1Encoder { backend }
+    }
+
+    /// Explicitly release the resources held by the current backend (e.g.
+    /// the native library context held by the default `FfiBackend`) ahead of
+    /// `Drop`. Idempotent: calling this more than once, or on an encoder that
+    /// has never been used, simply replaces the backend again.
+    pub fn free(&mut self) {
+        self.backend = Box::new(FreedBackend);
+    }
 
     pub fn get_version(&self) -> String {
-        let c_str: &CStr = unsafe { CStr::from_ptr(gs1_encoder_getVersion()) };
-        c_str.to_str().unwrap().to_owned()
+        self.backend.get_version()
     }
 
     pub fn get_err_markup(&self) -> String {
-        let c_str: &CStr = unsafe { CStr::from_ptr(gs1_encoder_getErrMarkup(self.ctx)) };
-        c_str.to_str().unwrap().to_owned()
+        self.backend.get_err_markup()
     }
 
     pub fn get_sym(&self) -> Symbology {
-        let raw = unsafe { gs1_encoder_getSym(self.ctx) };
-        unsafe { std::mem::transmute(raw) }
+        self.backend.get_sym()
     }
 
     pub fn set_sym(&mut self, sym: Symbology) -> Result<(), GS1EncoderError> {
-        let ret = unsafe { gs1_encoder_setSym(self.ctx, sym as c_int) };
-        if !ret {
-            return Err(GS1EncoderError::GS1ParameterError(self.get_err_msg()));
+        if !self.backend.set_sym(sym) {
+            return Err(GS1EncoderError::GS1ParameterError(self.backend.get_err_msg()));
         }
         Ok(())
     }
 
     pub fn get_add_check_digit(&self) -> bool {
-        unsafe { gs1_encoder_getAddCheckDigit(self.ctx) }
+        self.backend.get_add_check_digit()
     }
 
     pub fn set_add_check_digit(&mut self, value: bool) -> Result<(), GS1EncoderError> {
-        let ret = unsafe { gs1_encoder_setAddCheckDigit(self.ctx, value) };
-        if !ret {
-            return Err(GS1EncoderError::GS1ParameterError(self.get_err_msg()));
+        if !self.backend.set_add_check_digit(value) {
+            return Err(GS1EncoderError::GS1ParameterError(self.backend.get_err_msg()));
         }
         Ok(())
     }
 
     pub fn get_permit_unknown_ais(&self) -> bool {
-        unsafe { gs1_encoder_getPermitUnknownAIs(self.ctx) }
+        self.backend.get_permit_unknown_ais()
     }
 
     pub fn set_permit_unknown_ais(&mut self, value: bool) -> Result<(), GS1EncoderError> {
-        let ret = unsafe { gs1_encoder_setPermitUnknownAIs(self.ctx, value) };
-        if !ret {
-            return Err(GS1EncoderError::GS1ParameterError(self.get_err_msg()));
+        if !self.backend.set_permit_unknown_ais(value) {
+            return Err(GS1EncoderError::GS1ParameterError(self.backend.get_err_msg()));
         }
         Ok(())
     }
 
     pub fn get_permit_zero_suppressed_gtin_in_dl_uris(&self) -> bool {
-        unsafe { gs1_encoder_getPermitZeroSuppressedGTINinDLuris(self.ctx) }
+        self.backend.get_permit_zero_suppressed_gtin_in_dl_uris()
     }
 
     pub fn set_permit_zero_suppressed_gtin_in_dl_uris(
         &mut self,
         value: bool,
     ) -> Result<(), GS1EncoderError> {
-        let ret = unsafe { gs1_encoder_setPermitZeroSuppressedGTINinDLuris(self.ctx, value) };
-        if !ret {
-            return Err(GS1EncoderError::GS1ParameterError(self.get_err_msg()));
+        if !self
+            .backend
+            .set_permit_zero_suppressed_gtin_in_dl_uris(value)
+        {
+            return Err(GS1EncoderError::GS1ParameterError(self.backend.get_err_msg()));
         }
         Ok(())
     }
 
     pub fn get_validation_enabled(&self, validation: Validation) -> bool {
-        unsafe { gs1_encoder_getValidationEnabled(self.ctx, validation as c_int) }
+        self.backend.get_validation_enabled(validation)
     }
 
     pub fn set_validation_enabled(
@@ -191,10 +654,8 @@ impl GS1Encoder {
         validation: Validation,
         enabled: bool,
     ) -> Result<(), GS1EncoderError> {
-        let ret =
-            unsafe { gs1_encoder_setValidationEnabled(self.ctx, validation as c_int, enabled) };
-        if !ret {
-            return Err(GS1EncoderError::GS1ParameterError(self.get_err_msg()));
+        if !self.backend.set_validation_enabled(validation, enabled) {
+            return Err(GS1EncoderError::GS1ParameterError(self.backend.get_err_msg()));
         }
         Ok(())
     }
@@ -210,108 +671,368 @@ impl GS1Encoder {
     }
 
     pub fn set_include_data_titles_in_hri(&mut self, value: bool) -> Result<(), GS1EncoderError> {
-        let ret = unsafe { gs1_encoder_setIncludeDataTitlesInHRI(self.ctx, value) };
-        if !ret {
-            return Err(GS1EncoderError::GS1ParameterError(self.get_err_msg()));
+        if !self.backend.set_include_data_titles_in_hri(value) {
+            return Err(GS1EncoderError::GS1ParameterError(self.backend.get_err_msg()));
         }
         Ok(())
     }
 
     pub fn get_include_data_titles_in_hri(&self) -> bool {
-        unsafe { gs1_encoder_getIncludeDataTitlesInHRI(self.ctx) }
+        self.backend.get_include_data_titles_in_hri()
     }
 
     pub fn get_data_str(&self) -> String {
-        let c_str: &CStr = unsafe { CStr::from_ptr(gs1_encoder_getDataStr(self.ctx)) };
-        c_str.to_str().unwrap().to_owned()
+        self.backend.get_data_str()
     }
 
     pub fn set_data_str(&mut self, value: &str) -> Result<(), GS1EncoderError> {
-        let c_str = CString::new(value).unwrap();
-        let ret = unsafe { gs1_encoder_setDataStr(self.ctx, c_str.as_ptr() as *const c_char) };
-        if !ret {
-            return Err(GS1EncoderError::GS1ParameterError(self.get_err_msg()));
+        if !self.backend.set_data_str(value) {
+            return Err(GS1EncoderError::GS1ParameterError(self.backend.get_err_msg()));
         }
         Ok(())
     }
 
     pub fn get_ai_data_str(&self) -> Option<String> {
-        let ptr = unsafe { gs1_encoder_getAIdataStr(self.ctx) };
-        if ptr.is_null() {
-            return None;
-        }
-        let c_str: &CStr = unsafe { CStr::from_ptr(ptr) };
-        Some(c_str.to_str().unwrap().to_owned())
+        self.backend.get_ai_data_str()
     }
 
     pub fn set_ai_data_str(&mut self, value: &str) -> Result<(), GS1EncoderError> {
-        let c_str = CString::new(value).unwrap();
-        let ret = unsafe { gs1_encoder_setAIdataStr(self.ctx, c_str.as_ptr() as *const c_char) };
-        if !ret {
-            return Err(GS1EncoderError::GS1ParameterError(self.get_err_msg()));
+        if !self.backend.set_ai_data_str(value) {
+            return Err(GS1EncoderError::GS1ParameterError(self.backend.get_err_msg()));
         }
         Ok(())
     }
 
     pub fn get_scan_data(&self) -> Option<String> {
-        let ptr = unsafe { gs1_encoder_getScanData(self.ctx) };
-        if ptr.is_null() {
-            return None;
-        }
-        let c_str: &CStr = unsafe { CStr::from_ptr(ptr) };
-        Some(c_str.to_str().unwrap().to_owned())
+        self.backend.get_scan_data()
     }
 
     pub fn set_scan_data(&mut self, value: &str) -> Result<(), GS1EncoderError> {
-        let c_str = CString::new(value).unwrap();
-        let ret = unsafe { gs1_encoder_setScanData(self.ctx, c_str.as_ptr() as *const c_char) };
-        if !ret {
-            return Err(GS1EncoderError::GS1ScanDataError(self.get_err_msg()));
+        if !self.backend.set_scan_data(value) {
+            return Err(GS1EncoderError::GS1ScanDataError(self.backend.get_err_msg()));
         }
         Ok(())
     }
 
     pub fn get_dl_uri(&self, stem: Option<&str>) -> Result<String, GS1EncoderError> {
-        let c_stem = stem.map(|s| CString::new(s).unwrap());
-        let stem_ptr = c_stem
-            .as_ref()
-            .map_or(ptr::null(), |s| s.as_ptr() as *const c_char);
-        let ptr = unsafe { gs1_encoder_getDLuri(self.ctx, stem_ptr) };
-        if ptr.is_null() {
-            return Err(GS1EncoderError::GS1DigitalLinkError(self.get_err_msg()));
-        }
-        let c_str: &CStr = unsafe { CStr::from_ptr(ptr) };
-        Ok(c_str.to_str().unwrap().to_owned())
+        self.backend
+            .get_dl_uri(stem)
+            .ok_or_else(|| GS1EncoderError::GS1DigitalLinkError(self.backend.get_err_msg()))
     }
 
     pub fn get_dl_ignored_query_params(&self) -> Vec<String> {
-        let ptr: *const *const c_char = ptr::null();
-        let size = unsafe { gs1_encoder_getDLignoredQueryParams(self.ctx, &ptr) };
-        let mut params = Vec::with_capacity(size as usize);
-        for i in 0..size {
-            let c_buf = unsafe { ptr::read(ptr.offset(i as isize)) };
-            let c_str: &CStr = unsafe { CStr::from_ptr(c_buf) };
-            params.push(c_str.to_str().unwrap().to_owned());
-        }
-        params
+        self.backend.get_dl_ignored_query_params()
     }
 
     pub fn get_hri(&self) -> Vec<String> {
-        let ptr: *const *const c_char = ptr::null();
-        let size = unsafe { gs1_encoder_getHRI(self.ctx, &ptr) };
-        let mut hri = Vec::with_capacity(size as usize);
-        for i in 0..size {
-            let c_buf = unsafe { ptr::read(ptr.offset(i as isize)) };
-            let c_str: &CStr = unsafe { CStr::from_ptr(c_buf) };
-            hri.push(c_str.to_str().unwrap().to_owned());
+        self.backend.get_hri()
+    }
+
+    /// Parse the AI data and HRI of the currently-processed message into a
+    /// structured `Vec<ExtractedAI>`, instead of forcing callers to re-parse
+    /// the pre-formatted strings returned by `get_ai_data_str` and `get_hri`.
+    ///
+    /// Returns an empty vector if the current data does not represent valid
+    /// AI data.
+    pub fn get_ai_elements(&self) -> Vec<ExtractedAI> {
+        let ai_data_str = match self.get_ai_data_str() {
+            Some(s) => s,
+            None => return Vec::new(),
+        };
+
+        let pairs = Self::parse_ai_pairs(&ai_data_str);
+        let fnc1_required = Self::fnc1_required_flags(&self.get_data_str(), &pairs);
+        let include_titles = self.get_include_data_titles_in_hri();
+        let hri = self.get_hri();
+
+        pairs
+            .into_iter()
+            .zip(fnc1_required)
+            .enumerate()
+            .map(|(i, ((ai, value), fnc1_required))| {
+                let data_title = if include_titles {
+                    hri.get(i).and_then(|line| {
+                        let marker = format!("({ai})");
+                        line.find(&marker)
+                            .map(|pos| line[..pos].trim_end().to_string())
+                    })
+                } else {
+                    None
+                };
+                ExtractedAI {
+                    ai,
+                    value,
+                    data_title,
+                    fnc1_required,
+                }
+            })
+            .collect()
+    }
+
+    /// Split an AI data string such as `(01)12312312312319(99)TESTING123`
+    /// into its `(ai, value)` pairs.
+    fn parse_ai_pairs(ai_data_str: &str) -> Vec<(String, String)> {
+        let mut pairs = Vec::new();
+        let mut rest = ai_data_str;
+        while let Some(open) = rest.find('(') {
+            let after_open = &rest[open + 1..];
+            let Some(close) = after_open.find(')') else {
+                break;
+            };
+            let ai = after_open[..close].to_string();
+            let after_close = &after_open[close + 1..];
+            let value_end = after_close.find('(').unwrap_or(after_close.len());
+            let value = after_close[..value_end].to_string();
+            pairs.push((ai, value));
+            rest = &after_close[value_end..];
         }
-        hri
+        pairs
+    }
+
+    /// Determine, for each `(ai, value)` pair, whether it was terminated by
+    /// an explicit FNC1 separator (`^`) in the raw data string. The raw data
+    /// string is only split on FNC1 where an element could not otherwise be
+    /// unambiguously delimited, so an element that falls at such a split
+    /// (other than the last) required one.
+    ///
+    /// `data_str` is only in this `^`-delimited element-string form when the
+    /// input was supplied (or can be rendered by the native library) that
+    /// way; `get_data_str()` instead returns the verbatim input when it was
+    /// set via a Digital Link URI (see `test_set_dl_uri`), so there is no
+    /// FNC1 information to recover in that case. Rather than matching
+    /// AI+value tokens against an unrelated string and reporting whatever
+    /// that happens to produce, detect the mismatch up front and report
+    /// `false` for every pair.
+    fn fnc1_required_flags(data_str: &str, pairs: &[(String, String)]) -> Vec<bool> {
+        if !data_str.starts_with('^') {
+            return vec![false; pairs.len()];
+        }
+
+        let segments: Vec<&str> = data_str.trim_start_matches('^').split('^').collect();
+        let last_segment = segments.len().saturating_sub(1);
+
+        let mut flags = Vec::with_capacity(pairs.len());
+        let mut segment = 0;
+        let mut remaining = segments.first().copied().unwrap_or("");
+
+        for (ai, value) in pairs {
+            let token = format!("{ai}{value}");
+            remaining = remaining.strip_prefix(token.as_str()).unwrap_or(remaining);
+            if remaining.is_empty() && segment < last_segment {
+                flags.push(true);
+                segment += 1;
+                remaining = segments.get(segment).copied().unwrap_or("");
+            } else {
+                flags.push(false);
+            }
+        }
+        flags
+    }
+
+    /// Collect the full result of processing the current input into a single
+    /// `GS1Message`, suitable for serialising as JSON (with the `serde`
+    /// feature enabled) to hand off to a web service or message queue.
+    pub fn to_message(&self) -> Result<GS1Message, GS1EncoderError> {
+        Ok(GS1Message {
+            symbology: self.get_sym(),
+            data_str: self.get_data_str(),
+            ai_elements: self.get_ai_elements(),
+            hri: self.get_hri(),
+            dl_uri: self.get_dl_uri(None).ok(),
+            dl_ignored_query_params: self.get_dl_ignored_query_params(),
+            scan_data: self.get_scan_data(),
+        })
     }
 }
 
-impl Drop for GS1Encoder {
-    fn drop(&mut self) {
-        self.free();
+/// The full result of processing an input with a `GS1Encoder`: the
+/// symbology, the raw data string, the parsed AI elements, the HRI lines,
+/// the Digital Link URI (where one can be formed), any ignored query
+/// parameters and the scan data, collected into a single document.
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[derive(Debug, Clone, PartialEq)]
+pub struct GS1Message {
+    pub symbology: Symbology,
+    pub data_str: String,
+    pub ai_elements: Vec<ExtractedAI>,
+    pub hri: Vec<String>,
+    pub dl_uri: Option<String>,
+    pub dl_ignored_query_params: Vec<String>,
+    pub scan_data: Option<String>,
+}
+
+/// The input that a `GS1EncoderBuilder` applies to configure a `GS1Encoder`,
+/// corresponding to one of `set_data_str`, `set_ai_data_str`,
+/// `set_scan_data` or a Digital Link URI (which, like any other data string,
+/// is also applied via `set_data_str`).
+#[derive(Debug, Clone)]
+enum BuilderInput {
+    DataStr(String),
+    AiDataStr(String),
+    ScanData(String),
+    DlUri(String),
+}
+
+/// A fluent, self-documenting builder for a fully-configured `GS1Encoder`,
+/// so that callers do not have to chain a series of fallible setters and
+/// check each one individually. Settings are applied by `build()` in the
+/// order required by the native library: the symbology and general options,
+/// then the validation toggles, then the HRI data-title option, and all of
+/// them before the input data, since the input is parsed according to the
+/// options and validations already in force.
+#[derive(Debug, Clone, Default)]
+pub struct GS1EncoderBuilder {
+    sym: Option<Symbology>,
+    add_check_digit: Option<bool>,
+    permit_unknown_ais: Option<bool>,
+    permit_zero_suppressed_gtin_in_dl_uris: Option<bool>,
+    validations: Vec<(Validation, bool)>,
+    include_data_titles_in_hri: Option<bool>,
+    input: Option<BuilderInput>,
+}
+
+impl GS1EncoderBuilder {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn sym(mut self, sym: Symbology) -> Self {
+        self.sym = Some(sym);
+        self
+    }
+
+    pub fn add_check_digit(mut self, value: bool) -> Self {
+        self.add_check_digit = Some(value);
+        self
+    }
+
+    pub fn permit_unknown_ais(mut self, value: bool) -> Self {
+        self.permit_unknown_ais = Some(value);
+        self
+    }
+
+    pub fn permit_zero_suppressed_gtin_in_dl_uris(mut self, value: bool) -> Self {
+        self.permit_zero_suppressed_gtin_in_dl_uris = Some(value);
+        self
+    }
+
+    pub fn validation(mut self, validation: Validation, enabled: bool) -> Self {
+        self.validations.push((validation, enabled));
+        self
+    }
+
+    pub fn include_data_titles_in_hri(mut self, value: bool) -> Self {
+        self.include_data_titles_in_hri = Some(value);
+        self
+    }
+
+    /// Set the input as a raw data string, e.g. `^0112312312312319` (with
+    /// `^` representing FNC1), or a plain (non-AI) data string.
+    pub fn data_str(mut self, value: impl Into<String>) -> Self {
+        self.input = Some(BuilderInput::DataStr(value.into()));
+        self
+    }
+
+    /// Set the input as an AI data string, e.g. `(01)12312312312319`.
+    pub fn ai_data_str(mut self, value: impl Into<String>) -> Self {
+        self.input = Some(BuilderInput::AiDataStr(value.into()));
+        self
+    }
+
+    /// Set the input as barcode scan data, e.g. as read by a scanner.
+    pub fn scan_data(mut self, value: impl Into<String>) -> Self {
+        self.input = Some(BuilderInput::ScanData(value.into()));
+        self
+    }
+
+    /// Set the input as a GS1 Digital Link URI.
+    pub fn dl_uri(mut self, value: impl Into<String>) -> Self {
+        self.input = Some(BuilderInput::DlUri(value.into()));
+        self
+    }
+
+    /// Apply the accumulated settings, in order, against a freshly
+    /// initialised encoder backed by the native `gs1_encoder_*` C library,
+    /// returning the first `GS1EncoderError` encountered together with the
+    /// name of the setting that failed.
+    pub fn build(self) -> Result<GS1Encoder, GS1EncoderError> {
+        self.build_with_backend(Box::new(FfiBackend::new()?))
+    }
+
+    /// As `build()`, but against a caller-supplied `GS1Backend`.
+    pub fn build_with_backend(
+        self,
+        backend: Box<dyn GS1Backend>,
+    ) -> Result<GS1Encoder, GS1EncoderError> {
+        let mut encoder = GS1Encoder::with_backend(backend);
+
+        if let Some(sym) = self.sym {
+            encoder.set_sym(sym).map_err(|e| Self::context("sym", e))?;
+        }
+        if let Some(value) = self.add_check_digit {
+            encoder
+                .set_add_check_digit(value)
+                .map_err(|e| Self::context("add_check_digit", e))?;
+        }
+        if let Some(value) = self.permit_unknown_ais {
+            encoder
+                .set_permit_unknown_ais(value)
+                .map_err(|e| Self::context("permit_unknown_ais", e))?;
+        }
+        if let Some(value) = self.permit_zero_suppressed_gtin_in_dl_uris {
+            encoder
+                .set_permit_zero_suppressed_gtin_in_dl_uris(value)
+                .map_err(|e| Self::context("permit_zero_suppressed_gtin_in_dl_uris", e))?;
+        }
+        for (validation, enabled) in self.validations {
+            encoder
+                .set_validation_enabled(validation, enabled)
+                .map_err(|e| Self::context("validation", e))?;
+        }
+        if let Some(value) = self.include_data_titles_in_hri {
+            encoder
+                .set_include_data_titles_in_hri(value)
+                .map_err(|e| Self::context("include_data_titles_in_hri", e))?;
+        }
+
+        match self.input {
+            None => {}
+            Some(BuilderInput::DataStr(value) | BuilderInput::DlUri(value)) => {
+                encoder
+                    .set_data_str(&value)
+                    .map_err(|e| Self::context("data_str", e))?;
+            }
+            Some(BuilderInput::AiDataStr(value)) => {
+                encoder
+                    .set_ai_data_str(&value)
+                    .map_err(|e| Self::context("ai_data_str", e))?;
+            }
+            Some(BuilderInput::ScanData(value)) => {
+                encoder
+                    .set_scan_data(&value)
+                    .map_err(|e| Self::context("scan_data", e))?;
+            }
+        }
+
+        Ok(encoder)
+    }
+
+    fn context(field: &str, err: GS1EncoderError) -> GS1EncoderError {
+        let msg = err.to_string();
+        match err {
+            GS1EncoderError::GS1GeneralError(_) => {
+                GS1EncoderError::GS1GeneralError(format!("{field}: {msg}"))
+            }
+            GS1EncoderError::GS1ParameterError(_) => {
+                GS1EncoderError::GS1ParameterError(format!("{field}: {msg}"))
+            }
+            GS1EncoderError::GS1ScanDataError(_) => {
+                GS1EncoderError::GS1ScanDataError(format!("{field}: {msg}"))
+            }
+            GS1EncoderError::GS1DigitalLinkError(_) => {
+                GS1EncoderError::GS1DigitalLinkError(format!("{field}: {msg}"))
+            }
+        }
     }
 }
 
@@ -588,6 +1309,140 @@ mod tests {
         );
     }
 
+    #[test]
+    fn test_get_ai_elements() {
+        let mut gs1encoder = GS1Encoder::new().unwrap();
+
+        gs1encoder
+            .set_ai_data_str("(01)12312312312333(10)ABC123(99)XYZ")
+            .unwrap();
+
+        assert_eq!(
+            gs1encoder.get_ai_elements(),
+            vec![
+                ExtractedAI {
+                    ai: "01".to_string(),
+                    value: "12312312312333".to_string(),
+                    data_title: None,
+                    fnc1_required: false,
+                },
+                ExtractedAI {
+                    ai: "10".to_string(),
+                    value: "ABC123".to_string(),
+                    data_title: None,
+                    fnc1_required: true,
+                },
+                ExtractedAI {
+                    ai: "99".to_string(),
+                    value: "XYZ".to_string(),
+                    data_title: None,
+                    fnc1_required: false,
+                },
+            ]
+        );
+
+        gs1encoder.set_include_data_titles_in_hri(true).unwrap();
+        let elements = gs1encoder.get_ai_elements();
+        assert_eq!(elements[0].data_title.as_deref(), Some("GTIN"));
+        assert_eq!(elements[1].ai, "10");
+        assert_eq!(elements[1].value, "ABC123");
+    }
+
+    #[test]
+    fn test_get_ai_elements_non_ai_data() {
+        let mut gs1encoder = GS1Encoder::new().unwrap();
+
+        gs1encoder.set_data_str("TESTING").unwrap();
+        assert!(gs1encoder.get_ai_elements().is_empty());
+    }
+
+    #[test]
+    fn test_get_ai_elements_dl_uri_data_str() {
+        // When the data was supplied via a Digital Link URI, `get_data_str()`
+        // returns the verbatim URI rather than the internal `^`-delimited
+        // element-string form (see `test_set_dl_uri`), so there is no FNC1
+        // separator information to recover from it.
+        let backend = MockBackend {
+            data_str: "https://id.example.org/test/01/12312312312333/10/ABC123?99=XYZ"
+                .to_string(),
+            ai_data_str: Some("(01)12312312312333(10)ABC123(99)XYZ".to_string()),
+            hri: vec![
+                "(01) 12312312312333".to_string(),
+                "(10) ABC123".to_string(),
+                "(99) XYZ".to_string(),
+            ],
+            ..MockBackend::new()
+        };
+        let gs1encoder = GS1Encoder::with_backend(Box::new(backend));
+
+        let elements = gs1encoder.get_ai_elements();
+        assert_eq!(elements.len(), 3);
+        assert!(
+            elements.iter().all(|e| !e.fnc1_required),
+            "expected fnc1_required to degrade to false when data_str isn't in \
+             the internal token form, got {elements:?}"
+        );
+    }
+
+    #[test]
+    fn test_to_message() {
+        let mut gs1encoder = GS1Encoder::new().unwrap();
+
+        gs1encoder
+            .set_ai_data_str("(01)12312312312319(99)TESTING123")
+            .unwrap();
+
+        let message = gs1encoder.to_message().unwrap();
+        assert_eq!(message.symbology, Symbology::None);
+        assert_eq!(message.data_str, gs1encoder.get_data_str());
+        assert_eq!(message.ai_elements, gs1encoder.get_ai_elements());
+        assert_eq!(message.hri, gs1encoder.get_hri());
+        assert_eq!(message.dl_uri.as_deref(), Some("https://id.gs1.org/01/12312312312319?99=TESTING123"));
+        assert_eq!(message.scan_data, None);
+    }
+
+    #[test]
+    fn test_to_message_dl_uri_data_str() {
+        // `to_message` builds `ai_elements` via `get_ai_elements`, so it
+        // inherits the same degrade-rather-than-guess behaviour when
+        // `data_str` was populated from a Digital Link URI instead of the
+        // internal `^`-delimited element-string form.
+        let backend = MockBackend {
+            data_str: "https://id.example.org/test/01/12312312312319?99=TESTING123"
+                .to_string(),
+            ai_data_str: Some("(01)12312312312319(99)TESTING123".to_string()),
+            dl_uri: Some("https://id.gs1.org/01/12312312312319?99=TESTING123".to_string()),
+            hri: vec![
+                "(01) 12312312312319".to_string(),
+                "(99) TESTING123".to_string(),
+            ],
+            ..MockBackend::new()
+        };
+        let gs1encoder = GS1Encoder::with_backend(Box::new(backend));
+
+        let message = gs1encoder.to_message().unwrap();
+        assert!(message.ai_elements.iter().all(|e| !e.fnc1_required));
+        assert_eq!(
+            message.data_str,
+            "https://id.example.org/test/01/12312312312319?99=TESTING123"
+        );
+    }
+
+    #[cfg(feature = "serde")]
+    #[test]
+    fn test_to_message_json_roundtrip() {
+        let mut gs1encoder = GS1Encoder::new().unwrap();
+
+        gs1encoder
+            .set_ai_data_str("(01)12312312312319(99)TESTING123")
+            .unwrap();
+
+        let message = gs1encoder.to_message().unwrap();
+        let json = serde_json::to_string(&message).unwrap();
+        let parsed: GS1Message = serde_json::from_str(&json).unwrap();
+        assert_eq!(parsed, message);
+    }
+
     #[test]
     fn test_err_markup() {
         let mut gs1encoder = GS1Encoder::new().unwrap();
@@ -607,4 +1462,141 @@ mod tests {
             gs1encoder.get_err_markup()
         );
     }
+
+    #[test]
+    fn test_mock_backend_basic_state() {
+        let mut gs1encoder = GS1Encoder::with_backend(Box::new(MockBackend::new()));
+
+        assert_eq!(gs1encoder.get_sym(), Symbology::None);
+        gs1encoder.set_sym(Symbology::Qr).unwrap();
+        assert_eq!(gs1encoder.get_sym(), Symbology::Qr);
+
+        gs1encoder.set_data_str("^0112312312312319").unwrap();
+        assert_eq!(gs1encoder.get_data_str(), "^0112312312312319");
+
+        assert!(gs1encoder.get_validation_enabled(Validation::RequisiteAis));
+        gs1encoder
+            .set_validation_enabled(Validation::RequisiteAis, false)
+            .unwrap();
+        assert!(!gs1encoder.get_validation_enabled(Validation::RequisiteAis));
+    }
+
+    #[test]
+    fn test_free_is_idempotent() {
+        let mut gs1encoder = GS1Encoder::with_backend(Box::new(MockBackend::new()));
+
+        gs1encoder.free();
+        gs1encoder.free(); // calling free() again must not panic
+    }
+
+    #[test]
+    fn test_use_after_free_panics() {
+        let mut gs1encoder = GS1Encoder::with_backend(Box::new(MockBackend::new()));
+        gs1encoder.free();
+
+        let result = std::panic::catch_unwind(std::panic::AssertUnwindSafe(|| {
+            gs1encoder.get_sym()
+        }));
+        assert!(result.is_err());
+    }
+
+    #[test]
+    fn test_mock_backend_canned_values() {
+        let backend = MockBackend {
+            ai_data_str: Some("(01)12312312312319".to_string()),
+            dl_uri: Some("https://id.gs1.org/01/12312312312319".to_string()),
+            hri: vec!["(01) 12312312312319".to_string()],
+            ..MockBackend::new()
+        };
+        let gs1encoder = GS1Encoder::with_backend(Box::new(backend));
+
+        assert_eq!(
+            gs1encoder.get_ai_data_str().unwrap(),
+            "(01)12312312312319"
+        );
+        assert_eq!(
+            gs1encoder.get_dl_uri(None).unwrap(),
+            "https://id.gs1.org/01/12312312312319"
+        );
+        assert_eq!(gs1encoder.get_hri(), vec!["(01) 12312312312319"]);
+    }
+
+    #[test]
+    fn test_mock_backend_fail_setters() {
+        let backend = MockBackend {
+            err_msg: "mock parameter error".to_string(),
+            fail_setters: true,
+            ..MockBackend::new()
+        };
+        let mut gs1encoder = GS1Encoder::with_backend(Box::new(backend));
+
+        let err = gs1encoder.set_data_str("^0112312312312319").unwrap_err();
+        assert!(matches!(err, GS1EncoderError::GS1ParameterError(_)));
+        assert_eq!(err.to_string(), "mock parameter error");
+    }
+
+    #[test]
+    fn test_builder() {
+        let gs1encoder = GS1EncoderBuilder::new()
+            .sym(Symbology::Qr)
+            .include_data_titles_in_hri(true)
+            .ai_data_str("(01)12312312312319(99)TESTING123")
+            .build()
+            .unwrap();
+
+        assert_eq!(gs1encoder.get_sym(), Symbology::Qr);
+        assert_eq!(
+            gs1encoder.get_hri(),
+            vec!["GTIN (01) 12312312312319", "INTERNAL (99) TESTING123"]
+        );
+        assert_eq!(
+            gs1encoder.get_ai_data_str().unwrap(),
+            "(01)12312312312319(99)TESTING123"
+        );
+    }
+
+    #[test]
+    fn test_builder_validation_before_input() {
+        let gs1encoder = GS1EncoderBuilder::new()
+            .validation(Validation::RequisiteAis, false)
+            .data_str("^0212312312312319")
+            .build()
+            .unwrap();
+
+        assert_eq!(gs1encoder.get_data_str(), "^0212312312312319");
+        assert!(!gs1encoder.get_validation_enabled(Validation::RequisiteAis));
+    }
+
+    #[test]
+    fn test_builder_reports_failing_field() {
+        let backend = MockBackend {
+            err_msg: "mock parameter error".to_string(),
+            fail_setters: true,
+            ..MockBackend::new()
+        };
+
+        let err = match GS1EncoderBuilder::new()
+            .add_check_digit(true)
+            .build_with_backend(Box::new(backend))
+        {
+            Err(e) => e,
+            Ok(_) => panic!("expected build() to fail"),
+        };
+
+        assert!(matches!(err, GS1EncoderError::GS1ParameterError(_)));
+        assert_eq!(err.to_string(), "add_check_digit: mock parameter error");
+    }
+
+    #[test]
+    fn test_builder_with_mock_backend() {
+        let gs1encoder = GS1EncoderBuilder::new()
+            .dl_uri("https://id.example.org/test/01/12312312312319")
+            .build_with_backend(Box::new(MockBackend::new()))
+            .unwrap();
+
+        assert_eq!(
+            gs1encoder.get_data_str(),
+            "https://id.example.org/test/01/12312312312319"
+        );
+    }
 }